@@ -0,0 +1,106 @@
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use serde_json::Value;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+
+use crate::config::{QemuConfig, config_file};
+
+#[derive(Args, Debug)]
+pub struct CtlArgs {
+    /// Configuration name of the running VM.
+    ///
+    /// The VM must have been started with `vex exec --qmp` so a control
+    /// socket is recorded in its configuration.
+    pub name: String,
+
+    /// QMP command to send.
+    ///
+    /// # Examples
+    ///
+    /// Pause a running guest:
+    /// ```shell
+    /// vex ctl my-vm stop
+    /// ```
+    ///
+    /// Send a raw QMP command with arguments:
+    /// ```shell
+    /// vex ctl my-vm '{"execute":"savevm","arguments":{"name":"snap1"}}'
+    /// ```
+    pub command: String,
+}
+
+pub fn ctl_command(name: String, command: String) -> Result<()> {
+    let config_path = config_file(&name)?;
+    if !config_path.exists() {
+        anyhow::bail!(
+            "Configuration '{}' does not exist. Create it first with 'vex save'",
+            name
+        );
+    }
+
+    let config_json = fs::read_to_string(&config_path).context("Failed to read config file")?;
+    let config: QemuConfig =
+        serde_json::from_str(&config_json).context("Failed to deserialize configuration")?;
+
+    let socket = config.qmp_socket.with_context(|| {
+        format!(
+            "No QMP socket recorded for '{}'. Start it with 'vex exec --qmp {}' first",
+            name, name
+        )
+    })?;
+
+    let stream = UnixStream::connect(&socket)
+        .with_context(|| format!("Failed to connect to QMP socket '{}'", socket))?;
+    let mut writer = stream.try_clone().context("Failed to clone QMP socket")?;
+    let mut reader = BufReader::new(stream);
+
+    // Read the initial greeting banner.
+    read_qmp_line(&mut reader)?;
+
+    // Complete the capabilities handshake.
+    send_raw_line(&mut writer, r#"{"execute":"qmp_capabilities"}"#)?;
+    let caps_reply = read_qmp_line(&mut reader)?;
+    if let Some(error) = caps_reply.get("error") {
+        bail!("QMP capabilities negotiation failed: {}", error);
+    }
+
+    // Send the requested command, either a bare command name or a raw QMP
+    // JSON object for commands that need arguments (e.g. `savevm`).
+    let request = if command.trim_start().starts_with('{') {
+        serde_json::from_str::<Value>(&command).context("Command is not valid JSON")?
+    } else {
+        serde_json::json!({ "execute": command })
+    };
+    send_raw_line(&mut writer, &request.to_string())?;
+
+    let reply = read_qmp_line(&mut reader)?;
+    if let Some(error) = reply.get("error") {
+        bail!("QMP command failed: {}", error);
+    }
+
+    println!("{}", serde_json::to_string_pretty(&reply)?);
+
+    Ok(())
+}
+
+/// Read one newline-delimited JSON object from the QMP socket.
+fn read_qmp_line(reader: &mut BufReader<UnixStream>) -> Result<Value> {
+    let mut line = String::new();
+    let bytes_read = reader
+        .read_line(&mut line)
+        .context("Failed to read from QMP socket")?;
+    if bytes_read == 0 {
+        bail!("QMP socket closed unexpectedly");
+    }
+    serde_json::from_str(&line).context("Failed to parse QMP reply as JSON")
+}
+
+/// Write a raw line (with trailing newline) to the QMP socket.
+fn send_raw_line(writer: &mut UnixStream, line: &str) -> Result<()> {
+    writer
+        .write_all(line.as_bytes())
+        .and_then(|_| writer.write_all(b"\n"))
+        .context("Failed to write to QMP socket")
+}
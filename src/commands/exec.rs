@@ -1,11 +1,15 @@
 use anyhow::{Context, Result};
 use clap::Args;
 use regex::Regex;
+use std::collections::HashMap;
 use std::fs;
+use std::net::TcpListener;
 use std::process::Command;
 
-use crate::config::{QemuConfig, config_file};
+use crate::config::{QemuConfig, config_dir, config_file};
+use crate::features::expand_features;
 use crate::utils::qemu::get_qemu_version;
+use crate::vfio::resolve_vfio_args;
 
 #[derive(Args, Debug)]
 pub struct ExecArgs {
@@ -26,9 +30,9 @@ pub struct ExecArgs {
 
     /// Start QEMU in debug mode.
     ///
-    /// This appends `-s -S` to the QEMU arguments:
-    /// - `-s`: Shorthand for -gdb tcp::1234
-    /// - `-S`: Freeze CPU at startup
+    /// This appends `-gdb tcp::<port> -S` to the QEMU arguments, freezing
+    /// the CPU at startup and exposing a GDB stub on `<port>` (see
+    /// `--gdb-port`; a free port is chosen automatically if not given).
     ///
     /// Useful for attaching a debugger (GDB) before the OS boots.
     #[arg(short = 'd', long = "debug")]
@@ -37,10 +41,41 @@ pub struct ExecArgs {
     /// Show full QEMU command line arguments before starting.
     #[arg(short = 'f', long = "full")]
     pub full: bool,
+
+    /// Attach a QMP control socket so the VM can be steered with `vex ctl`.
+    #[arg(long = "qmp")]
+    pub qmp: bool,
+
+    /// GDB port to use in debug mode.
+    ///
+    /// If omitted, a free port is chosen automatically, which lets multiple
+    /// VMs be debugged concurrently without colliding.
+    #[arg(long = "gdb-port")]
+    pub gdb_port: Option<u16>,
+
+    /// Override a `${VAR}` placeholder for this run only, as `KEY=VALUE`.
+    ///
+    /// Repeatable. Checked before environment variables and before any
+    /// `${VAR:-default}` fallback.
+    ///
+    /// # Examples
+    ///
+    /// ```shell
+    /// vex exec my-vm -p MEM=8G -p DISK=/data/alt.qcow2
+    /// ```
+    #[arg(short = 'p', long = "param")]
+    pub params: Vec<String>,
 }
 
-/// TODO: Currently the debug port is fixed at 1234. It should be adaptive or configurable.
-pub fn exec_command(name: String, debug: bool, full: bool) -> Result<()> {
+pub fn exec_command(
+    name: String,
+    debug: bool,
+    full: bool,
+    qmp: bool,
+    gdb_port: Option<u16>,
+    params: Vec<String>,
+) -> Result<()> {
+    let overrides = parse_param_overrides(&params)?;
     let config_path = config_file(&name)?;
     if !config_path.exists() {
         anyhow::bail!(
@@ -50,7 +85,7 @@ pub fn exec_command(name: String, debug: bool, full: bool) -> Result<()> {
     }
 
     let config_json = fs::read_to_string(&config_path).context("Failed to read config file")?;
-    let config: QemuConfig =
+    let mut config: QemuConfig =
         serde_json::from_str(&config_json).context("Failed to deserialize configuration")?;
 
     if let Some(saved_ver) = &config.qemu_version {
@@ -68,19 +103,51 @@ pub fn exec_command(name: String, debug: bool, full: bool) -> Result<()> {
             _ => {} // Versions match, all good
         }
     }
-    let mut exec_args = config.args.clone();
+    let raw_args = match &config.lua_script {
+        Some(script_path) => build_lua_args(script_path, &name, &config, &overrides)?,
+        None => config.args.clone(),
+    };
+
+    // Declarative features expand into flags prepended ahead of the user's
+    // own args, so features can be overridden/tuned by raw args that follow.
+    let mut exec_args = expand_features(&config.features, &name, true)?;
+    exec_args.extend(raw_args);
 
     // Substitute parameters in args
-    exec_args = substitute_params(&exec_args);
+    exec_args = substitute_params(&exec_args, &overrides)?;
 
-    if debug {
-        // Add debug parameters
-        exec_args.push("-s".to_string());
-        exec_args.push("-S".to_string());
+    // Resolve structured VFIO passthrough descriptors to -device flags.
+    exec_args.extend(resolve_vfio_args(&config.vfio)?);
+
+    if qmp {
+        let socket_path = config_dir()?.join(format!("{}.qmp.sock", name));
+        // QEMU refuses to bind a Unix socket that already exists.
+        let _ = fs::remove_file(&socket_path);
+
+        exec_args.push("-qmp".to_string());
+        exec_args.push(format!("unix:{},server,nowait", socket_path.display()));
+
+        config.qmp_socket = Some(socket_path.display().to_string());
+        let config_json =
+            serde_json::to_string_pretty(&config).context("Failed to serialize configuration")?;
+        fs::write(&config_path, config_json).context("Failed to record QMP socket in config")?;
     }
 
+    let chosen_gdb_port = if debug {
+        let port = match gdb_port {
+            Some(port) => port,
+            None => find_free_gdb_port()?,
+        };
+        exec_args.push("-gdb".to_string());
+        exec_args.push(format!("tcp::{}", port));
+        exec_args.push("-S".to_string());
+        Some(port)
+    } else {
+        None
+    };
+
     // Print startup message
-    print_startup_message(&name, &config, &exec_args, debug, full);
+    print_startup_message(&name, &config, &exec_args, full, chosen_gdb_port);
 
     let status = Command::new(&config.qemu_bin)
         .args(&exec_args)
@@ -97,13 +164,37 @@ pub fn exec_command(name: String, debug: bool, full: bool) -> Result<()> {
     Ok(())
 }
 
+/// Build the QEMU argument list from a config's Lua script.
+#[cfg(feature = "lua")]
+fn build_lua_args(
+    script_path: &str,
+    name: &str,
+    config: &QemuConfig,
+    overrides: &HashMap<String, String>,
+) -> Result<Vec<String>> {
+    crate::lua::build_args_from_script(script_path, name, config, overrides)
+}
+
+#[cfg(not(feature = "lua"))]
+fn build_lua_args(
+    _script_path: &str,
+    _name: &str,
+    _config: &QemuConfig,
+    _overrides: &HashMap<String, String>,
+) -> Result<Vec<String>> {
+    anyhow::bail!(
+        "This configuration uses a Lua script, but vex was built without the 'lua' feature. \
+         Rebuild with `cargo build --features lua`."
+    )
+}
+
 /// Print a user-friendly startup message
 fn print_startup_message(
     name: &str,
     config: &QemuConfig,
     args: &[String],
-    debug: bool,
     full: bool,
+    gdb_port: Option<u16>,
 ) {
     // Build the header
     let header = if let Some(desc) = &config.desc {
@@ -121,22 +212,83 @@ fn print_startup_message(
     }
 
     // Show debug info if in debug mode
-    if debug {
+    if let Some(port) = gdb_port {
         println!("  Mode: DEBUG");
-        println!("  GDB server: localhost:1234");
-        println!("\nðŸ’¡ You can connect with: gdb -ex 'target remote localhost:1234'");
+        println!("  GDB server: localhost:{}", port);
+        println!(
+            "\nðŸ’¡ You can connect with: gdb -ex 'target remote localhost:{}'",
+            port
+        );
     }
 }
 
-/// Substitute parameters in arguments using regex
-pub(crate) fn substitute_params(args: &[String]) -> Vec<String> {
-    let re = Regex::new(r"\$\{([^}]+)\}").unwrap();
-    args.iter()
-        .map(|arg| {
-            re.replace_all(arg, |caps: &regex::Captures| {
-                std::env::var(&caps[1]).unwrap_or_else(|_| format!("${{{}}}", &caps[1]))
-            })
-            .to_string()
+/// Probe for a free TCP port to run the GDB stub on, so multiple VMs can be
+/// debugged concurrently without colliding.
+fn find_free_gdb_port() -> Result<u16> {
+    let listener =
+        TcpListener::bind("127.0.0.1:0").context("Failed to probe a free TCP port for GDB")?;
+    Ok(listener.local_addr()?.port())
+}
+
+/// Parse repeated `-p KEY=VALUE` flags into a lookup map.
+fn parse_param_overrides(params: &[String]) -> Result<HashMap<String, String>> {
+    params
+        .iter()
+        .map(|param| {
+            param
+                .split_once('=')
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .with_context(|| format!("Invalid -p value '{}', expected KEY=VALUE", param))
         })
         .collect()
 }
+
+/// Substitute `${VAR}`, `${VAR:-default}` and `${VAR:?message}` placeholders
+/// in arguments, consulting `overrides` before environment variables.
+pub(crate) fn substitute_params(
+    args: &[String],
+    overrides: &HashMap<String, String>,
+) -> Result<Vec<String>> {
+    let re = Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)(:([-?])([^}]*))?\}").unwrap();
+    args.iter()
+        .map(|arg| substitute_one(&re, arg, overrides))
+        .collect()
+}
+
+fn substitute_one(re: &Regex, arg: &str, overrides: &HashMap<String, String>) -> Result<String> {
+    let mut result = String::new();
+    let mut last_end = 0;
+
+    for caps in re.captures_iter(arg) {
+        let whole = caps.get(0).unwrap();
+        result.push_str(&arg[last_end..whole.start()]);
+        last_end = whole.end();
+
+        let var = &caps[1];
+        let sigil = caps.get(3).map(|m| m.as_str());
+        let fallback = caps.get(4).map(|m| m.as_str()).unwrap_or("");
+        let resolved = overrides
+            .get(var)
+            .cloned()
+            .or_else(|| std::env::var(var).ok());
+
+        let value = match sigil {
+            Some("-") => match resolved.filter(|v| !v.is_empty()) {
+                Some(v) => v,
+                None => fallback.to_string(),
+            },
+            Some("?") => resolved.with_context(|| {
+                if fallback.is_empty() {
+                    format!("{} is not set", var)
+                } else {
+                    fallback.to_string()
+                }
+            })?,
+            _ => resolved.unwrap_or_else(|| whole.as_str().to_string()),
+        };
+        result.push_str(&value);
+    }
+    result.push_str(&arg[last_end..]);
+
+    Ok(result)
+}
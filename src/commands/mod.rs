@@ -1,4 +1,5 @@
 pub mod completions;
+pub mod ctl;
 pub mod exec;
 pub mod list;
 pub mod print;
@@ -7,6 +8,7 @@ pub mod rename;
 pub mod save;
 
 pub use completions::{CompletionsArgs, completions_command};
+pub use ctl::{CtlArgs, ctl_command};
 pub use exec::{ExecArgs, exec_command};
 pub use list::{ListArgs, list_command};
 pub use print::{PrintArgs, print_command};
@@ -36,6 +38,9 @@ pub enum Commands {
     /// Execute a saved configuration
     Exec(ExecArgs),
 
+    /// Send a QMP control command to a running VM
+    Ctl(CtlArgs),
+
     /// Generate shell completion scripts
     Completions(CompletionsArgs),
 }
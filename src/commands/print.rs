@@ -3,6 +3,8 @@ use clap::Args;
 use std::fs;
 
 use crate::config::{QemuConfig, config_file};
+use crate::features::expand_features;
+use crate::vfio::resolve_vfio_args;
 
 #[derive(Args, Debug)]
 pub struct PrintArgs {
@@ -50,6 +52,31 @@ pub fn print_command(name: String) -> Result<()> {
     println!("  {}", config.qemu_bin);
     println!();
 
+    // Print declarative features and the flags they expand into
+    if !config.features.is_empty() {
+        println!("Features:");
+        println!("  {}", config.features.join(", "));
+        println!();
+
+        println!("Resolved Feature Flags:");
+        match expand_features(&config.features, &name, false) {
+            Ok(flags) if flags.is_empty() => println!("  (none)"),
+            Ok(flags) => println!("  {}", flags.join(" ")),
+            Err(err) => println!("  Error: {}", err),
+        }
+        println!();
+    }
+
+    // Print resolved VFIO passthrough devices
+    if !config.vfio.is_empty() {
+        println!("VFIO Passthrough Devices:");
+        match resolve_vfio_args(&config.vfio) {
+            Ok(flags) => println!("  {}", flags.join(" ")),
+            Err(err) => println!("  Error: {}", err),
+        }
+        println!();
+    }
+
     // Print startup arguments
     println!("Startup Arguments:");
     if config.args.is_empty() {
@@ -0,0 +1,80 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use std::fs;
+
+use crate::config::{QemuConfig, config_file};
+use crate::features::validate_feature_names;
+use crate::utils::io::prompt_user_default_no;
+use crate::utils::qemu::get_qemu_version;
+
+#[derive(Args, Debug)]
+pub struct SaveArgs {
+    /// Name to save this configuration under.
+    ///
+    /// # Examples
+    ///
+    /// ```shell
+    /// vex save my-vm -- -m 4G -enable-kvm
+    /// ```
+    pub name: String,
+
+    /// Path to the QEMU binary to use.
+    #[arg(short = 'b', long = "bin", default_value = "qemu-system-x86_64")]
+    pub qemu_bin: String,
+
+    /// Optional description for this configuration.
+    #[arg(short = 'd', long = "desc")]
+    pub desc: Option<String>,
+
+    /// Force save without confirmation, overwriting an existing configuration.
+    #[arg(short = 'f', long = "force")]
+    pub force: bool,
+
+    /// Declarative feature to expand into QEMU flags at exec time (e.g.
+    /// `uefi`, `spice`). Repeatable.
+    #[arg(long = "feature")]
+    pub features: Vec<String>,
+
+    /// Raw QEMU arguments, passed through as-is.
+    #[arg(last = true)]
+    pub args: Vec<String>,
+}
+
+pub fn save_command(
+    name: String,
+    qemu_bin: String,
+    desc: Option<String>,
+    force: bool,
+    features: Vec<String>,
+    args: Vec<String>,
+) -> Result<()> {
+    validate_feature_names(&features)?;
+
+    let config_path = config_file(&name)?;
+    if config_path.exists() && !force {
+        println!("Configuration '{}' already exists, overwrite? [y/N]", name);
+        if !prompt_user_default_no()? {
+            println!("Save cancelled");
+            return Ok(());
+        }
+    }
+
+    let config = QemuConfig {
+        qemu_bin: qemu_bin.clone(),
+        args,
+        desc,
+        qemu_version: get_qemu_version(&qemu_bin),
+        qmp_socket: None,
+        lua_script: None,
+        features,
+        vfio: Vec::new(),
+    };
+
+    let config_json =
+        serde_json::to_string_pretty(&config).context("Failed to serialize configuration")?;
+    fs::write(&config_path, config_json).context("Failed to save config file")?;
+
+    println!("Configuration '{}' saved", name);
+
+    Ok(())
+}
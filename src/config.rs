@@ -0,0 +1,90 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A saved QEMU launch configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QemuConfig {
+    /// Path to the QEMU binary to invoke (e.g. `qemu-system-x86_64`).
+    pub qemu_bin: String,
+
+    /// Raw QEMU command-line arguments, in order.
+    pub args: Vec<String>,
+
+    /// Optional human-readable description shown by `vex list`/`vex print`.
+    pub desc: Option<String>,
+
+    /// QEMU version detected when the configuration was saved, used to warn
+    /// about drift at `exec` time.
+    pub qemu_version: Option<String>,
+
+    /// Path to the QMP Unix control socket, recorded when the VM was last
+    /// launched with `--qmp`. Consumed by `vex ctl` to steer the live guest.
+    #[serde(default)]
+    pub qmp_socket: Option<String>,
+
+    /// Path to a Lua script that builds the QEMU argument list at `exec`
+    /// time instead of using the static `args` array. Requires the `lua`
+    /// Cargo feature.
+    #[serde(default)]
+    pub lua_script: Option<String>,
+
+    /// Declarative feature names (e.g. `uefi`, `spice`) expanded into
+    /// concrete QEMU flags at `exec` time. See [`crate::features`].
+    #[serde(default)]
+    pub features: Vec<String>,
+
+    /// VFIO PCI passthrough devices, resolved to `-device vfio-pci,...`
+    /// flags at `exec` time. See [`crate::vfio`].
+    #[serde(default)]
+    pub vfio: Vec<VfioDevice>,
+}
+
+/// A single VFIO PCI passthrough descriptor.
+///
+/// Identify the device either by an explicit PCI `addr` (e.g. `0b:00.3`) or
+/// by `vendor`/`device` IDs, which are resolved to a BDF at `exec` time so
+/// the same config works across machines where addresses differ.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VfioDevice {
+    /// Explicit PCI address, e.g. `0b:00.3`. Takes priority over
+    /// `vendor`/`device` when set.
+    #[serde(default)]
+    pub addr: Option<String>,
+
+    /// PCI vendor ID, e.g. `10de`.
+    #[serde(default)]
+    pub vendor: Option<String>,
+
+    /// PCI device ID, e.g. `2504`.
+    #[serde(default)]
+    pub device: Option<String>,
+
+    /// Which match to use (0-based) when `vendor`/`device` match more than
+    /// one installed card.
+    #[serde(default)]
+    pub index: usize,
+
+    /// Pass `x-vga=on` so this device can be used as the primary display.
+    #[serde(default)]
+    pub graphics: bool,
+}
+
+/// Directory where all configuration files are stored.
+///
+/// Resolves to `<config_dir>/vex`, creating it if necessary.
+pub fn config_dir() -> Result<PathBuf> {
+    let mut dir = dirs::config_dir().context("Could not determine config directory")?;
+    dir.push("vex");
+    if !dir.exists() {
+        std::fs::create_dir_all(&dir).context("Failed to create config directory")?;
+    }
+    Ok(dir)
+}
+
+/// Path to the JSON configuration file for a given configuration name.
+pub fn config_file(name: &str) -> Result<PathBuf> {
+    let mut path = config_dir()?;
+    path.push(format!("{}.json", name));
+    Ok(path)
+}
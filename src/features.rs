@@ -0,0 +1,118 @@
+use anyhow::{Context, Result, bail};
+use std::fs;
+
+use crate::config::config_dir;
+
+const OVMF_CODE: &str = "/usr/share/OVMF/OVMF_CODE.fd";
+const OVMF_VARS_TEMPLATE: &str = "/usr/share/OVMF/OVMF_VARS.fd";
+
+/// All feature names Vex knows how to expand into QEMU flags.
+pub const SUPPORTED_FEATURES: &[&str] = &[
+    "uefi",
+    "spice",
+    "virtio-gpu",
+    "audio-pulse",
+    "looking-glass",
+];
+
+/// Check that every feature name is recognized, without resolving flags.
+pub fn validate_feature_names(features: &[String]) -> Result<()> {
+    for feature in features {
+        if !SUPPORTED_FEATURES.contains(&feature.as_str()) {
+            bail!(
+                "Unknown feature '{}'. Supported features: {}",
+                feature,
+                SUPPORTED_FEATURES.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Expand a config's declarative `features` list into concrete QEMU args,
+/// in order, for the named VM.
+///
+/// `seed` controls whether resolving a feature may create supporting files
+/// on disk (e.g. a per-VM UEFI vars pflash). Pass `true` only from the exec
+/// path; display paths like `vex print` must pass `false` so they resolve
+/// flag strings without mutating the filesystem.
+pub fn expand_features(features: &[String], vm_name: &str, seed: bool) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    for feature in features {
+        match feature.as_str() {
+            "uefi" => args.extend(expand_uefi(vm_name, seed)?),
+            "spice" => args.extend(expand_spice(vm_name)?),
+            "virtio-gpu" => args.extend(expand_virtio_gpu()),
+            "audio-pulse" => args.extend(expand_audio_pulse()),
+            "looking-glass" => args.extend(expand_looking_glass()),
+            other => bail!(
+                "Unknown feature '{}'. Supported features: {}",
+                other,
+                SUPPORTED_FEATURES.join(", ")
+            ),
+        }
+    }
+    Ok(args)
+}
+
+/// UEFI firmware: read-only code pflash plus a per-VM writable vars pflash,
+/// seeded from the system template the first time a VM uses it (only when
+/// `seed` is set; display-only callers just resolve the path).
+fn expand_uefi(vm_name: &str, seed: bool) -> Result<Vec<String>> {
+    let vars_path = config_dir()?.join(format!("{}-uefi-vars.fd", vm_name));
+    if seed && !vars_path.exists() {
+        fs::copy(OVMF_VARS_TEMPLATE, &vars_path).with_context(|| {
+            format!(
+                "Failed to seed per-VM UEFI vars file at {:?} from {}",
+                vars_path, OVMF_VARS_TEMPLATE
+            )
+        })?;
+    }
+
+    Ok(vec![
+        "-drive".to_string(),
+        format!("if=pflash,format=raw,readonly=on,file={}", OVMF_CODE),
+        "-drive".to_string(),
+        format!("if=pflash,format=raw,file={}", vars_path.display()),
+    ])
+}
+
+/// SPICE display over a per-VM Unix socket, plus the virtio-serial channel
+/// SPICE agents (clipboard sharing, etc.) ride on.
+fn expand_spice(vm_name: &str) -> Result<Vec<String>> {
+    let socket = config_dir()?.join(format!("{}.spice.sock", vm_name));
+
+    Ok(vec![
+        "-spice".to_string(),
+        format!("unix,addr={},disable-ticketing=on", socket.display()),
+        "-device".to_string(),
+        "virtio-serial".to_string(),
+    ])
+}
+
+fn expand_virtio_gpu() -> Vec<String> {
+    vec!["-device".to_string(), "virtio-gpu-pci".to_string()]
+}
+
+fn expand_audio_pulse() -> Vec<String> {
+    vec![
+        "-device".to_string(),
+        "intel-hda".to_string(),
+        "-device".to_string(),
+        "hda-duplex,audiodev=pa0".to_string(),
+        "-audiodev".to_string(),
+        "pa,id=pa0".to_string(),
+    ]
+}
+
+/// Shared memory device that the Looking Glass host application writes
+/// captured frames into for the guest client to read.
+fn expand_looking_glass() -> Vec<String> {
+    vec![
+        "-device".to_string(),
+        "ivshmem-plain,memdev=looking-glass".to_string(),
+        "-object".to_string(),
+        "memory-backend-file,id=looking-glass,mem-path=/dev/shm/looking-glass,size=128M,share=on"
+            .to_string(),
+    ]
+}
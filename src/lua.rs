@@ -0,0 +1,118 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use anyhow::{Context, Result};
+use mlua::{Lua, Variadic};
+
+use crate::config::QemuConfig;
+
+/// Run a Lua script that programmatically builds a QEMU argument list.
+///
+/// The script sees two globals:
+/// - `builder`: call `builder.arg(...)` with one or more strings to append
+///   to the QEMU argument vector, in order.
+/// - `instance`: a read-only table with `name`, `desc` and `params` (the
+///   `-p KEY=VALUE` overrides passed to `vex exec`).
+pub fn build_args_from_script(
+    script_path: &str,
+    name: &str,
+    config: &QemuConfig,
+    params: &HashMap<String, String>,
+) -> Result<Vec<String>> {
+    let script = std::fs::read_to_string(script_path)
+        .with_context(|| format!("Failed to read Lua script '{}'", script_path))?;
+
+    let lua = Lua::new();
+    let args = Rc::new(RefCell::new(Vec::<String>::new()));
+
+    let builder = lua
+        .create_table()
+        .context("Failed to create Lua builder table")?;
+    let args_for_closure = Rc::clone(&args);
+    let arg_fn = lua
+        .create_function(move |_, values: Variadic<String>| {
+            args_for_closure.borrow_mut().extend(values);
+            Ok(())
+        })
+        .context("Failed to create builder.arg function")?;
+    builder
+        .set("arg", arg_fn)
+        .context("Failed to attach builder.arg function")?;
+    lua.globals()
+        .set("builder", builder)
+        .context("Failed to expose builder table to Lua")?;
+
+    let instance = lua
+        .create_table()
+        .context("Failed to create Lua instance table")?;
+    instance
+        .set("name", name)
+        .context("Failed to set instance.name")?;
+    instance
+        .set("desc", config.desc.clone())
+        .context("Failed to set instance.desc")?;
+    let params_table = lua
+        .create_table()
+        .context("Failed to create instance.params table")?;
+    for (key, value) in params {
+        params_table
+            .set(key.as_str(), value.as_str())
+            .with_context(|| format!("Failed to set instance.params.{}", key))?;
+    }
+    instance
+        .set("params", params_table)
+        .context("Failed to set instance.params")?;
+    lua.globals()
+        .set("instance", instance)
+        .context("Failed to expose instance table to Lua")?;
+
+    lua.load(&script)
+        .exec()
+        .with_context(|| format!("Lua script '{}' failed", script_path))?;
+
+    // `arg_fn` holds its own `Rc::clone(&args)` and is owned by `lua`, which
+    // is still alive here, so `Rc::try_unwrap` would never succeed. Take the
+    // vec out of the shared cell instead of requiring sole ownership.
+    Ok(std::mem::take(&mut *args.borrow_mut()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_arg_calls_produce_the_args_vector() {
+        let mut script_path = std::env::temp_dir();
+        script_path.push(format!("vex-lua-test-{}.lua", std::process::id()));
+        std::fs::write(
+            &script_path,
+            r#"
+                builder.arg("-m", instance.params.mem or "2G")
+                builder.arg("-name", instance.name)
+            "#,
+        )
+        .unwrap();
+
+        let config = QemuConfig {
+            qemu_bin: "qemu-system-x86_64".to_string(),
+            args: Vec::new(),
+            desc: None,
+            qemu_version: None,
+            qmp_socket: None,
+            lua_script: None,
+            features: Vec::new(),
+            vfio: Vec::new(),
+        };
+        let mut params = HashMap::new();
+        params.insert("mem".to_string(), "4G".to_string());
+
+        let args =
+            build_args_from_script(script_path.to_str().unwrap(), "my-vm", &config, &params)
+                .unwrap();
+
+        std::fs::remove_file(&script_path).unwrap();
+
+        assert_eq!(args, vec!["-m", "4G", "-name", "my-vm"]);
+    }
+}
@@ -0,0 +1,43 @@
+mod commands;
+mod config;
+mod features;
+#[cfg(feature = "lua")]
+mod lua;
+mod utils;
+mod vfio;
+
+use anyhow::Result;
+use clap::Parser;
+
+use commands::{Cli, Commands};
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Commands::Save(args) => commands::save_command(
+            args.name,
+            args.qemu_bin,
+            args.desc,
+            args.force,
+            args.features,
+            args.args,
+        ),
+        Commands::Rename(args) => {
+            commands::rename_command(args.desc, args.force, args.old_name, args.new_name)
+        }
+        Commands::Rm(args) => commands::remove_command(args.name),
+        Commands::List(_) => commands::list_command(),
+        Commands::Print(args) => commands::print_command(args.name),
+        Commands::Exec(args) => commands::exec_command(
+            args.name,
+            args.debug,
+            args.full,
+            args.qmp,
+            args.gdb_port,
+            args.params,
+        ),
+        Commands::Ctl(args) => commands::ctl_command(args.name, args.command),
+        Commands::Completions(args) => commands::completions_command(args.shell),
+    }
+}
@@ -0,0 +1,15 @@
+use anyhow::{Context, Result};
+use std::io::{self, Write};
+
+/// Prompt the user with a yes/no question, defaulting to "no" on empty input.
+pub fn prompt_user_default_no() -> Result<bool> {
+    print!("> ");
+    io::stdout().flush().context("Failed to flush stdout")?;
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .context("Failed to read user input")?;
+
+    Ok(matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+}
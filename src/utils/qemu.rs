@@ -0,0 +1,14 @@
+use std::process::Command;
+
+/// Run `<bin> --version` and extract the version string from the first line.
+pub fn get_qemu_version(bin: &str) -> Option<String> {
+    let output = Command::new(bin).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let first_line = stdout.lines().next()?;
+    // Typical output: "QEMU emulator version 8.2.0"
+    first_line.split_whitespace().nth(3).map(String::from)
+}
@@ -0,0 +1,114 @@
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::VfioDevice;
+
+const PCI_DEVICES_DIR: &str = "/sys/bus/pci/devices";
+
+/// Resolve a config's VFIO device descriptors to `-device vfio-pci,...`
+/// flags, in order.
+pub fn resolve_vfio_args(devices: &[VfioDevice]) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    for dev in devices {
+        let bdf = normalize_bdf(&resolve_bdf(dev)?);
+        ensure_bound_to_vfio(&bdf)?;
+
+        let mut flag = format!("vfio-pci,host={}", bdf);
+        if dev.graphics {
+            flag.push_str(",x-vga=on");
+        }
+        args.push("-device".to_string());
+        args.push(flag);
+    }
+    Ok(args)
+}
+
+/// Resolve a descriptor to a PCI bus:device.function address.
+fn resolve_bdf(dev: &VfioDevice) -> Result<String> {
+    if let Some(addr) = &dev.addr {
+        return Ok(addr.clone());
+    }
+
+    let vendor = dev
+        .vendor
+        .as_deref()
+        .context("VFIO device descriptor needs either 'addr' or 'vendor'+'device'")?;
+    let device = dev
+        .device
+        .as_deref()
+        .context("VFIO device descriptor needs either 'addr' or 'vendor'+'device'")?;
+
+    let mut matches: Vec<String> = fs::read_dir(PCI_DEVICES_DIR)
+        .with_context(|| format!("Failed to read {}", PCI_DEVICES_DIR))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| ids_match(path, "vendor", vendor) && ids_match(path, "device", device))
+        .filter_map(|path| path.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .collect();
+    matches.sort();
+
+    matches.get(dev.index).cloned().with_context(|| {
+        if matches.is_empty() {
+            format!(
+                "No PCI device found matching vendor={} device={}",
+                vendor, device
+            )
+        } else {
+            format!(
+                "VFIO index {} out of range: only {} device(s) match vendor={} device={}",
+                dev.index,
+                matches.len(),
+                vendor,
+                device
+            )
+        }
+    })
+}
+
+/// Compare the vendor/device ID file under a `/sys/bus/pci/devices/<bdf>`
+/// entry against an expected ID, ignoring `0x` prefix and case.
+fn ids_match(device_dir: &Path, file: &str, expected: &str) -> bool {
+    let expected = normalize_id(expected);
+    fs::read_to_string(device_dir.join(file))
+        .map(|id| normalize_id(id.trim()) == expected)
+        .unwrap_or(false)
+}
+
+fn normalize_id(id: &str) -> String {
+    id.trim_start_matches("0x").to_lowercase()
+}
+
+/// sysfs always names PCI device directories with the domain prefix
+/// (`0000:0b:00.3`), but a config's `addr` may be given in the shorter
+/// `bus:dev.fn` form (`0b:00.3`). Prepend the default domain when missing.
+fn normalize_bdf(bdf: &str) -> String {
+    if bdf.matches(':').count() < 2 {
+        format!("0000:{}", bdf)
+    } else {
+        bdf.to_string()
+    }
+}
+
+/// Error out unless the device is currently bound to the `vfio-pci` driver.
+///
+/// `bdf` must already be domain-qualified (see `normalize_bdf`).
+fn ensure_bound_to_vfio(bdf: &str) -> Result<()> {
+    let driver_link = PathBuf::from(PCI_DEVICES_DIR).join(bdf).join("driver");
+    let driver = fs::read_link(&driver_link)
+        .ok()
+        .and_then(|path| path.file_name().map(|n| n.to_string_lossy().into_owned()));
+
+    match driver.as_deref() {
+        Some("vfio-pci") => Ok(()),
+        Some(other) => bail!(
+            "PCI device {} is bound to '{}', not 'vfio-pci'. Bind it to vfio-pci first",
+            bdf,
+            other
+        ),
+        None => bail!(
+            "PCI device {} is not bound to any driver. Bind it to vfio-pci first",
+            bdf
+        ),
+    }
+}